@@ -1,6 +1,10 @@
-use std::io::{self};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader as StdBufReader};
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use clap::Parser;
@@ -11,100 +15,598 @@ use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::text::{Span, Line};
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::Terminal;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::{TcpListener, TcpStream};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName};
+use tokio_rustls::rustls::{ClientConfig, RootCertStore, ServerConfig};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+/// Outbound senders for every peer currently connected to a server-mode room,
+/// keyed by a display id (a socket address for TCP, a synthetic label for
+/// Unix sockets) so a leaving peer can remove itself.
+type Peers = Arc<Mutex<HashMap<String, tokio::sync::mpsc::UnboundedSender<String>>>>;
+
+/// The wire protocol: one JSON object per line. Replaces the old
+/// `"{name}: {msg}\n"` concatenation so a message body containing a
+/// newline, or a peer naming itself after someone else, can't be
+/// misinterpreted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Message {
+    from: String,
+    ts: u64,
+    kind: MessageKind,
+    body: String,
+}
 
-#[derive(Parser, Debug)]
-#[command(author, version, about = "1:1 chat TUI using ratatui + sockets", long_about = None)]
-struct Args {
-    /// mode: server or client
-    #[arg(short, long, default_value = "server")]
-    mode: String,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum MessageKind {
+    Msg,
+    Join,
+    Leave,
+}
+
+impl Message {
+    fn new(from: String, kind: MessageKind, body: String) -> Self {
+        Message { from, ts: now_millis(), kind, body }
+    }
 
-    /// address to bind/connect, like 127.0.0.1:9000
-    #[arg(short, long, default_value = "127.0.0.1:9000")]
-    addr: String,
+    /// Serializes this message as a single terminated line ready to be
+    /// written to a socket.
+    fn to_wire(&self) -> String {
+        format!("{}\n", serde_json::to_string(self).expect("Message always serializes"))
+    }
 
-    /// your display name
-    #[arg(short, long, default_value = "you")]
-    name: String,
+    /// Renders this message the way it should appear in the TUI.
+    fn render(&self) -> String {
+        let time = format_ts(self.ts);
+        match self.kind {
+            MessageKind::Msg => format!("[{time}] {}: {}", self.from, self.body),
+            MessageKind::Join => format!("[{time}] --- {} joined the room ---", self.from),
+            MessageKind::Leave => format!("[{time}] --- {} left the room ---", self.from),
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn format_ts(ts_millis: u64) -> String {
+    let secs = ts_millis / 1000;
+    format!("{:02}:{:02}:{:02}", (secs / 3600) % 24, (secs / 60) % 60, secs % 60)
+}
+
+/// Renders a line read off the wire. Malformed or pre-protocol lines are
+/// passed through unchanged so older peers (or a stray plain-text line)
+/// still show up instead of being dropped.
+fn render_line(raw: &str) -> String {
+    match serde_json::from_str::<Message>(raw) {
+        Ok(msg) => msg.render(),
+        Err(_) => raw.to_string(),
+    }
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Computes the hex-encoded HMAC-SHA256 tag over `data` keyed by the shared
+/// `--auth-key` secret.
+fn hmac_hex(key: &str, data: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Verifies a hex-encoded HMAC-SHA256 tag over `data` against the shared
+/// `--auth-key` secret in constant time, so a peer probing the handshake
+/// can't learn anything about the expected tag from how long rejection takes.
+fn hmac_verify(key: &str, data: &str, tag_hex: &str) -> bool {
+    let Ok(tag) = hex::decode(tag_hex) else { return false };
+    let mut mac = HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.verify_slice(&tag).is_ok()
+}
+
+fn generate_nonce() -> String {
+    let bytes: [u8; 16] = rand::random();
+    hex::encode(bytes)
+}
+
+/// Runs the pre-auth handshake over an already-connected, already-TLS'd (if
+/// applicable) socket: both sides exchange a `HELLO <nonce>` line, then
+/// prove knowledge of the shared secret by replying `AUTH <hmac(nonce)>`.
+/// Returns an error (and notifies `ui_tx`) if the peer's tag doesn't match,
+/// so the caller can drop the connection before any chat is exchanged.
+async fn authenticate<R: AsyncBufRead + Unpin, W: AsyncWrite + Unpin>(
+    lines: &mut Lines<R>,
+    writer: &mut W,
+    key: &str,
+    ui_tx: &Sender<String>,
+) -> anyhow::Result<()> {
+    let my_nonce = generate_nonce();
+    writer.write_all(format!("HELLO {my_nonce}\n").as_bytes()).await?;
+
+    let hello = lines
+        .next_line()
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("connection closed during auth handshake"))?;
+    let peer_nonce = hello
+        .strip_prefix("HELLO ")
+        .ok_or_else(|| anyhow::anyhow!("expected HELLO during auth handshake, got: {hello}"))?;
+
+    writer
+        .write_all(format!("AUTH {}\n", hmac_hex(key, peer_nonce)).as_bytes())
+        .await?;
+
+    let auth = lines
+        .next_line()
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("connection closed during auth handshake"))?;
+    let peer_tag = auth
+        .strip_prefix("AUTH ")
+        .ok_or_else(|| anyhow::anyhow!("expected AUTH during auth handshake, got: {auth}"))?;
+
+    if !hmac_verify(key, &my_nonce, peer_tag) {
+        ui_tx.send("--- authentication failed: key mismatch, dropping connection ---".into()).ok();
+        anyhow::bail!("authentication failed: key mismatch");
+    }
+
+    Ok(())
+}
+
+/// Which transport a connection should use, decided once from `Args` and
+/// threaded down into `run_server`/`run_client` so the socket-handling code
+/// stays oblivious to whether it's looking at a plain or a TLS stream.
+enum Transport {
+    Plain,
+    Tls,
+}
+
+/// Where to bind/connect, parsed once from `--addr`: either a TCP socket
+/// address or a filesystem path for a Unix domain socket.
+enum Target {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+/// `--addr` is a Unix socket path if it looks like one (contains a `/`) or
+/// simply doesn't parse as an `ip:port` socket address (hostnames such as
+/// `localhost:9000` are not resolved and so are taken as a path too).
+fn parse_target(addr: &str) -> Target {
+    if addr.contains('/') {
+        return Target::Unix(PathBuf::from(addr));
+    }
+    match addr.parse::<SocketAddr>() {
+        Ok(sock) => Target::Tcp(sock),
+        Err(_) => Target::Unix(PathBuf::from(addr)),
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "chat TUI using ratatui + sockets", long_about = None)]
+struct Args {
+    /// mode: server or client (default: server, overridable via --config)
+    #[arg(short, long)]
+    mode: Option<String>,
+
+    /// address to bind/connect: an `ip:port` pair, or a filesystem path to
+    /// use a Unix domain socket instead (e.g. /tmp/chat.sock). Default:
+    /// 127.0.0.1:9000, overridable via --config
+    #[arg(short, long)]
+    addr: Option<String>,
+
+    /// your display name (default: you, overridable via --config)
+    #[arg(short, long)]
+    name: Option<String>,
+
+    /// load defaults from a TOML config file; explicit flags above still win
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// wrap a TCP connection in TLS via tokio-rustls; has no effect when
+    /// --addr names a Unix domain socket
+    #[arg(long)]
+    tls: bool,
+
+    /// server mode: PEM certificate chain to present (required with --tls)
+    #[arg(long)]
+    cert: Option<PathBuf>,
+
+    /// server mode: PEM private key matching --cert (required with --tls)
+    #[arg(long)]
+    key: Option<PathBuf>,
+
+    /// client mode: PEM root CA used to verify the server (required with --tls)
+    #[arg(long)]
+    ca: Option<PathBuf>,
+
+    /// client mode: SNI domain name to verify against the server cert (required with --tls)
+    #[arg(long)]
+    domain: Option<String>,
+
+    /// shared secret for a pre-auth HMAC handshake; both sides must pass the
+    /// same value or the connection is rejected before any chat begins.
+    /// Named `--auth-key` rather than `--key` to avoid colliding with the
+    /// TLS private-key flag above.
+    #[arg(long)]
+    auth_key: Option<String>,
 }
 
 enum NetworkCommand {
     Send(String),
+    /// Updates the name the network side stamps onto outbound `Message`s,
+    /// so a `/name` change in the TUI actually changes what peers see instead
+    /// of only renaming the local echo.
+    Rename(String),
+    Shutdown,
+}
+
+/// Defaults loaded from `--config`'s TOML file. Every field is optional so a
+/// config can set only what it cares about; command-line flags always take
+/// priority over whatever is here. Room for a `tls`/`auth` table to be added
+/// once those features grow config-file knobs of their own.
+#[derive(Debug, Default, Deserialize)]
+struct Config {
+    mode: Option<String>,
+    addr: Option<String>,
+    name: Option<String>,
+}
+
+fn load_config(path: &Path) -> anyhow::Result<Config> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(toml::from_str(&text)?)
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
-    let addr: SocketAddr = args.addr.parse()?;
+    let config = match &args.config {
+        Some(path) => load_config(path)?,
+        None => Config::default(),
+    };
+
+    let mode = args.mode.or(config.mode).unwrap_or_else(|| "server".to_string());
+    let addr = args.addr.or(config.addr).unwrap_or_else(|| "127.0.0.1:9000".to_string());
+    let name = args.name.or(config.name).unwrap_or_else(|| "you".to_string());
+    let target = parse_target(&addr);
 
     let (net_tx, net_rx): (Sender<NetworkCommand>, Receiver<NetworkCommand>) = mpsc::channel();
     let (ui_tx, ui_rx): (Sender<String>, Receiver<String>) = mpsc::channel();
 
-    let name_clone = args.name.clone();
-    let mode = args.mode.clone();
-    tokio::spawn(async move {
+    let name_clone = name.clone();
+    let transport = if args.tls { Transport::Tls } else { Transport::Plain };
+    let cert = args.cert.clone();
+    let key = args.key.clone();
+    let ca = args.ca.clone();
+    let domain = args.domain.clone();
+    let auth_key = args.auth_key.clone();
+    let network_task = tokio::spawn(async move {
         if mode == "server" {
-            if let Err(e) = run_server(addr, name_clone, net_rx, ui_tx).await {
+            if let Err(e) = run_server(target, name_clone, transport, cert, key, auth_key, net_rx, ui_tx).await {
                 eprintln!("server error: {e}");
             }
         } else {
-            if let Err(e) = run_client(addr, name_clone, net_rx, ui_tx).await {
+            if let Err(e) = run_client(target, name_clone, transport, ca, domain, auth_key, net_rx, ui_tx).await {
                 eprintln!("client error: {e}");
             }
         }
     });
 
-    run_ui(ui_rx, net_tx, args.name)
+    let ui_result = run_ui(ui_rx, net_tx, name);
+
+    // Give the network task a chance to drain and flush whatever chat
+    // message was queued right before quitting instead of abandoning it
+    // mid-flight when the process exits.
+    network_task.await?;
+
+    ui_result
 }
 
 async fn run_server(
-    addr: SocketAddr,
+    target: Target,
     name: String,
+    transport: Transport,
+    cert: Option<PathBuf>,
+    key: Option<PathBuf>,
+    auth_key: Option<String>,
     net_rx: Receiver<NetworkCommand>,
     ui_tx: Sender<String>,
 ) -> anyhow::Result<()> {
-    println!("Starting server on {addr} - waiting for one connection...");
-    let listener = TcpListener::bind(addr).await?;
-    let (socket, peer) = listener.accept().await?;
-    println!("Client connected: {peer}");
-    ui_tx.send(format!("--- Connected: {peer} ---")).ok();
+    let peers: Peers = Arc::new(Mutex::new(HashMap::new()));
+    let shutdown = Arc::new(tokio::sync::Notify::new());
+
+    // Local chat input doesn't belong to any one peer connection, so drain it
+    // on its own blocking task and fan it out to whoever is currently joined.
+    // On Esc it drains anything still queued before telling the accept loop
+    // below to stop, so a message typed right before quitting isn't lost.
+    {
+        let peers = peers.clone();
+        let shutdown = shutdown.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut name = name;
+            while let Ok(cmd) = net_rx.recv() {
+                match cmd {
+                    NetworkCommand::Send(text) => {
+                        let msg = Message::new(name.clone(), MessageKind::Msg, text);
+                        broadcast(&peers, None, &msg.to_wire());
+                    }
+                    NetworkCommand::Rename(new_name) => name = new_name,
+                    NetworkCommand::Shutdown => {
+                        loop {
+                            match net_rx.try_recv() {
+                                Ok(NetworkCommand::Send(text)) => {
+                                    let msg = Message::new(name.clone(), MessageKind::Msg, text);
+                                    broadcast(&peers, None, &msg.to_wire());
+                                }
+                                Ok(NetworkCommand::Rename(new_name)) => name = new_name,
+                                Ok(NetworkCommand::Shutdown) | Err(_) => break,
+                            }
+                        }
+                        shutdown.notify_waiters();
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    // Handles for every spawned per-peer task, so a graceful shutdown can
+    // wait for their final flush (driven by `shutdown` below) instead of
+    // abandoning them when `run_server` returns and the process exits.
+    let mut peer_tasks: Vec<tokio::task::JoinHandle<()>> = Vec::new();
+
+    match target {
+        Target::Tcp(addr) => {
+            println!("Starting server on {addr} - waiting for connections...");
+            let listener = TcpListener::bind(addr).await?;
+
+            let acceptor = match transport {
+                Transport::Tls => {
+                    let cert = cert.ok_or_else(|| anyhow::anyhow!("--cert is required with --tls"))?;
+                    let key = key.ok_or_else(|| anyhow::anyhow!("--key is required with --tls"))?;
+                    Some(TlsAcceptor::from(Arc::new(load_server_tls_config(&cert, &key)?)))
+                }
+                Transport::Plain => None,
+            };
+
+            loop {
+                let (socket, peer) = tokio::select! {
+                    accepted = listener.accept() => accepted?,
+                    _ = shutdown.notified() => break,
+                };
+                let id = peer.to_string();
+                println!("Client connected: {id}");
+
+                let peers = peers.clone();
+                let ui_tx = ui_tx.clone();
+                let acceptor = acceptor.clone();
+                let auth_key = auth_key.clone();
+                let shutdown = shutdown.clone();
+                peer_tasks.push(tokio::spawn(async move {
+                    let result = match acceptor {
+                        Some(acceptor) => match acceptor.accept(socket).await {
+                            Ok(tls_socket) => handle_peer(tls_socket, id.clone(), auth_key, peers.clone(), ui_tx.clone(), shutdown).await,
+                            Err(e) => Err(e.into()),
+                        },
+                        None => handle_peer(socket, id.clone(), auth_key, peers.clone(), ui_tx.clone(), shutdown).await,
+                    };
+                    finish_peer(&peers, &ui_tx, &id, result);
+                }));
+            }
+        }
+        Target::Unix(path) => {
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+            println!("Starting server on {} - waiting for connections...", path.display());
+            let listener = UnixListener::bind(&path)?;
+            let mut next_id: usize = 0;
+
+            loop {
+                let (socket, _) = tokio::select! {
+                    accepted = listener.accept() => accepted?,
+                    _ = shutdown.notified() => break,
+                };
+                next_id += 1;
+                let id = format!("unix-peer-{next_id}");
+                println!("Client connected: {id}");
+
+                let peers = peers.clone();
+                let ui_tx = ui_tx.clone();
+                let auth_key = auth_key.clone();
+                let shutdown = shutdown.clone();
+                peer_tasks.push(tokio::spawn(async move {
+                    let result = handle_peer(socket, id.clone(), auth_key, peers.clone(), ui_tx.clone(), shutdown).await;
+                    finish_peer(&peers, &ui_tx, &id, result);
+                }));
+            }
+        }
+    };
+
+    // Every peer task exits on its own once it sees `shutdown`, after
+    // flushing whatever final broadcasts (e.g. the local operator's last
+    // message) were still queued for it.
+    for task in peer_tasks {
+        task.await.ok();
+    }
+
+    Ok(())
+}
+
+/// Handles one connected peer in server (group chat) mode: reads lines off
+/// its socket and rebroadcasts them to every other peer, while draining its
+/// own outbound queue fed by `broadcast`. Generic over the stream type so
+/// TCP, TLS-wrapped and Unix-domain sockets all work identically. The peer
+/// is only announced to the room once it's authenticated and actually in
+/// `peers` — a failed handshake must not produce a join/leave announcement.
+/// On `shutdown`, drains and writes out anything still queued for this peer
+/// (e.g. the local operator's last message) before returning, so the
+/// caller can await this task to know the flush is done.
+async fn handle_peer<S: AsyncRead + AsyncWrite + Unpin>(
+    socket: S,
+    id: String,
+    auth_key: Option<String>,
+    peers: Peers,
+    ui_tx: Sender<String>,
+    shutdown: Arc<tokio::sync::Notify>,
+) -> anyhow::Result<()> {
+    let (reader, mut writer) = tokio::io::split(socket);
+    let mut lines = BufReader::new(reader).lines();
+
+    if let Some(key) = &auth_key {
+        authenticate(&mut lines, &mut writer, key, &ui_tx).await?;
+    }
 
-    handle_socket(socket, name, net_rx, ui_tx).await
+    let (peer_tx, mut peer_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    peers.lock().unwrap().insert(id.clone(), peer_tx);
+    announce_join(&peers, &ui_tx, &id);
+
+    loop {
+        tokio::select! {
+            maybe = lines.next_line() => {
+                match maybe {
+                    Ok(Some(line)) => {
+                        ui_tx.send(render_line(&line)).ok();
+                        broadcast(&peers, Some(&id), &format!("{line}\n"));
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        ui_tx.send(format!("--- read error from {id}: {e} ---")).ok();
+                        break;
+                    }
+                }
+            }
+
+            Some(msg) = peer_rx.recv() => {
+                if let Err(e) = writer.write_all(msg.as_bytes()).await {
+                    ui_tx.send(format!("--- write error to {id}: {e} ---")).ok();
+                    break;
+                }
+            }
+
+            _ = shutdown.notified() => {
+                while let Ok(msg) = peer_rx.try_recv() {
+                    if let Err(e) = writer.write_all(msg.as_bytes()).await {
+                        ui_tx.send(format!("--- write error to {id}: {e} ---")).ok();
+                        break;
+                    }
+                }
+                writer.flush().await.ok();
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Pushes `line` to every peer in the room except `except` (pass `None` to
+/// reach everyone, used for locally-originated messages and join/leave
+/// announcements).
+fn broadcast(peers: &Peers, except: Option<&str>, line: &str) {
+    let peers = peers.lock().unwrap();
+    for (id, tx) in peers.iter() {
+        if except == Some(id.as_str()) {
+            continue;
+        }
+        tx.send(line.to_string()).ok();
+    }
+}
+
+/// Announces a newly accepted peer to the local TUI and the rest of the room.
+fn announce_join(peers: &Peers, ui_tx: &Sender<String>, id: &str) {
+    let msg = Message::new(id.to_string(), MessageKind::Join, String::new());
+    ui_tx.send(msg.render()).ok();
+    broadcast(peers, None, &msg.to_wire());
+}
+
+/// Removes a disconnected peer from the registry and announces its departure,
+/// surfacing `result`'s error (if any) to the local TUI first. A peer that
+/// never made it into `peers` (e.g. it failed `authenticate`) was never
+/// announced as joined either, so its departure isn't announced here.
+fn finish_peer(peers: &Peers, ui_tx: &Sender<String>, id: &str, result: anyhow::Result<()>) {
+    if let Err(e) = result {
+        ui_tx.send(format!("--- connection error with {id}: {e} ---")).ok();
+    }
+    let was_joined = peers.lock().unwrap().remove(id).is_some();
+    if was_joined {
+        let msg = Message::new(id.to_string(), MessageKind::Leave, String::new());
+        ui_tx.send(msg.render()).ok();
+        broadcast(peers, None, &msg.to_wire());
+    }
 }
 
 async fn run_client(
-    addr: SocketAddr,
+    target: Target,
     name: String,
+    transport: Transport,
+    ca: Option<PathBuf>,
+    domain: Option<String>,
+    auth_key: Option<String>,
     net_rx: Receiver<NetworkCommand>,
     ui_tx: Sender<String>,
 ) -> anyhow::Result<()> {
-    println!("Connecting to {addr}...");
-    let socket = TcpStream::connect(addr).await?;
-    println!("Connected to server");
-    ui_tx.send("--- Connected to server ---".into()).ok();
+    match target {
+        Target::Tcp(addr) => {
+            println!("Connecting to {addr}...");
+            let socket = TcpStream::connect(addr).await?;
+            println!("Connected to server");
+            ui_tx.send("--- Connected to server ---".into()).ok();
+
+            match transport {
+                Transport::Tls => {
+                    let ca = ca.ok_or_else(|| anyhow::anyhow!("--ca is required with --tls"))?;
+                    let domain = domain.ok_or_else(|| anyhow::anyhow!("--domain is required with --tls"))?;
+                    let connector = TlsConnector::from(Arc::new(load_client_tls_config(&ca)?));
+                    let server_name = ServerName::try_from(domain)?;
+                    let socket = connector.connect(server_name, socket).await?;
+                    handle_socket(socket, name, auth_key, net_rx, ui_tx).await
+                }
+                Transport::Plain => handle_socket(socket, name, auth_key, net_rx, ui_tx).await,
+            }
+        }
+        Target::Unix(path) => {
+            println!("Connecting to {}...", path.display());
+            let socket = UnixStream::connect(&path).await?;
+            println!("Connected to server");
+            ui_tx.send("--- Connected to server ---".into()).ok();
 
-    handle_socket(socket, name, net_rx, ui_tx).await
+            handle_socket(socket, name, auth_key, net_rx, ui_tx).await
+        }
+    }
 }
 
-async fn handle_socket(
-    socket: TcpStream,
+/// Generic over the stream type so both plain `TcpStream`s and TLS-wrapped
+/// ones work identically.
+async fn handle_socket<S: AsyncRead + AsyncWrite + Unpin>(
+    socket: S,
     name: String,
+    auth_key: Option<String>,
     net_rx: Receiver<NetworkCommand>,
     ui_tx: Sender<String>,
 ) -> anyhow::Result<()> {
-    let (reader, mut writer) = socket.into_split();
+    let (reader, mut writer) = tokio::io::split(socket);
     let mut lines = BufReader::new(reader).lines();
 
+    if let Some(key) = &auth_key {
+        authenticate(&mut lines, &mut writer, key, &ui_tx).await?;
+    }
+
     let ui_tx_clone = ui_tx.clone();
+    let mut name = name;
 
     loop {
         tokio::select! {
             maybe = lines.next_line() => {
                 match maybe {
                     Ok(Some(line)) => {
-                        ui_tx_clone.send(line).ok();
+                        ui_tx_clone.send(render_line(&line)).ok();
                     }
                     Ok(None) => {
                         ui_tx_clone.send("--- Connection closed by peer ---".into()).ok();
@@ -118,17 +620,29 @@ async fn handle_socket(
             }
 
             _ = tokio::time::sleep(Duration::from_millis(50)) => {
+                let mut shutting_down = false;
                 while let Ok(cmd) = net_rx.try_recv() {
                     match cmd {
-                        NetworkCommand::Send(msg) => {
-                            let out = format!("{}: {}\n", name, msg);
+                        NetworkCommand::Send(text) => {
+                            let out = Message::new(name.clone(), MessageKind::Msg, text).to_wire();
                             if let Err(e) = writer.write_all(out.as_bytes()).await {
                                 ui_tx_clone.send(format!("--- Socket write error: {e} ---")).ok();
                                 return Ok(());
                             }
                         }
+                        NetworkCommand::Rename(new_name) => {
+                            name = new_name;
+                        }
+                        NetworkCommand::Shutdown => {
+                            shutting_down = true;
+                        }
                     }
                 }
+                if shutting_down {
+                    writer.flush().await?;
+                    writer.shutdown().await?;
+                    break;
+                }
             }
         }
     }
@@ -136,6 +650,35 @@ async fn handle_socket(
     Ok(())
 }
 
+/// Builds a `ServerConfig` from a PEM certificate chain and private key for
+/// server-mode `--tls`.
+fn load_server_tls_config(cert_path: &Path, key_path: &Path) -> anyhow::Result<ServerConfig> {
+    let certs = rustls_pemfile::certs(&mut StdBufReader::new(File::open(cert_path)?))
+        .collect::<Result<Vec<CertificateDer>, _>>()?;
+    let key = rustls_pemfile::private_key(&mut StdBufReader::new(File::open(key_path)?))?
+        .ok_or_else(|| anyhow::anyhow!("no private key found in {}", key_path.display()))?;
+
+    let config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+    Ok(config)
+}
+
+/// Builds a `ClientConfig` trusting the given PEM root CA for client-mode
+/// `--tls`.
+fn load_client_tls_config(ca_path: &Path) -> anyhow::Result<ClientConfig> {
+    let mut root_store = RootCertStore::empty();
+    let ca_certs = rustls_pemfile::certs(&mut StdBufReader::new(File::open(ca_path)?))
+        .collect::<Result<Vec<CertificateDer>, _>>()?;
+    for cert in ca_certs {
+        root_store.add(cert)?;
+    }
+
+    let config = ClientConfig::builder()
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+    Ok(config)
+}
 
 fn run_ui(ui_rx: Receiver<String>, net_tx: Sender<NetworkCommand>, my_name: String) -> anyhow::Result<()> {
     enable_raw_mode()?;
@@ -153,12 +696,48 @@ fn run_ui(ui_rx: Receiver<String>, net_tx: Sender<NetworkCommand>, my_name: Stri
     res
 }
 
+/// A parsed line of chat input: either a slash command or plain chat text.
+#[derive(Debug, PartialEq)]
+enum Command {
+    Chat(String),
+    Quit,
+    Rename(String),
+    Me(String),
+    Help,
+    Unknown(String),
+}
+
+/// Parses one line of TUI input. A leading `/` introduces a command; its
+/// first word selects which one, and the rest of the line (trimmed) is its
+/// argument. Anything without a leading `/` is plain chat text.
+fn parse_command(input: &str) -> Command {
+    let input = input.trim();
+    let Some(rest) = input.strip_prefix('/') else {
+        return Command::Chat(input.to_string());
+    };
+
+    let mut parts = rest.splitn(2, ' ');
+    let name = parts.next().unwrap_or("");
+    let arg = parts.next().unwrap_or("").trim().to_string();
+    match name {
+        "quit" => Command::Quit,
+        "name" => Command::Rename(arg),
+        "me" => Command::Me(arg),
+        "help" => Command::Help,
+        other => Command::Unknown(other.to_string()),
+    }
+}
+
+const HELP_TEXT: &str =
+    "--- commands: /quit, /name <new name>, /me <action>, /help ---";
+
 fn ui_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     ui_rx: Receiver<String>,
     net_tx: Sender<NetworkCommand>,
     my_name: String,
 ) -> anyhow::Result<()> {
+    let mut my_name = my_name;
     let mut messages: Vec<String> = Vec::new();
     let mut input = String::new();
     let tick_rate = Duration::from_millis(100);
@@ -210,14 +789,48 @@ fn ui_loop(
                         KeyCode::Backspace => { input.pop(); }
                         KeyCode::Enter => {
                             if !input.trim().is_empty() {
-                                let to_send = input.clone();
-                                messages.push(format!("{}: {}", my_name, to_send));
-                                net_tx.send(NetworkCommand::Send(to_send)).ok();
+                                let line = input.clone();
                                 input.clear();
+                                match parse_command(&line) {
+                                    Command::Chat(text) => {
+                                        messages.push(format!("{my_name}: {text}"));
+                                        net_tx.send(NetworkCommand::Send(text)).ok();
+                                    }
+                                    Command::Quit => {
+                                        messages.push("--- Exiting ---".into());
+                                        net_tx.send(NetworkCommand::Shutdown).ok();
+                                        break;
+                                    }
+                                    Command::Rename(new_name) => {
+                                        if new_name.is_empty() {
+                                            messages.push("--- /name requires a new name, e.g. /name Alice ---".into());
+                                        } else {
+                                            net_tx
+                                                .send(NetworkCommand::Send(format!("is now known as {new_name}")))
+                                                .ok();
+                                            net_tx.send(NetworkCommand::Rename(new_name.clone())).ok();
+                                            messages.push(format!("--- {my_name} is now known as {new_name} ---"));
+                                            my_name = new_name;
+                                        }
+                                    }
+                                    Command::Me(action) => {
+                                        if action.is_empty() {
+                                            messages.push("--- /me requires an action, e.g. /me waves ---".into());
+                                        } else {
+                                            messages.push(format!("* {my_name} {action}"));
+                                            net_tx.send(NetworkCommand::Send(format!("*{action}*"))).ok();
+                                        }
+                                    }
+                                    Command::Help => messages.push(HELP_TEXT.into()),
+                                    Command::Unknown(cmd) => {
+                                        messages.push(format!("--- unknown command: /{cmd} (try /help) ---"));
+                                    }
+                                }
                             }
                         }
                         KeyCode::Esc => {
                             messages.push("--- Exiting ---".into());
+                            net_tx.send(NetworkCommand::Shutdown).ok();
                             break;
                         }
                         _ => {}
@@ -233,3 +846,61 @@ fn ui_loop(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_command_plain_text_is_chat() {
+        assert_eq!(parse_command("hello there"), Command::Chat("hello there".to_string()));
+    }
+
+    #[test]
+    fn parse_command_trims_surrounding_whitespace() {
+        assert_eq!(parse_command("  hi  "), Command::Chat("hi".to_string()));
+    }
+
+    #[test]
+    fn parse_command_recognizes_known_slash_commands() {
+        assert_eq!(parse_command("/quit"), Command::Quit);
+        assert_eq!(parse_command("/help"), Command::Help);
+        assert_eq!(parse_command("/name Alice"), Command::Rename("Alice".to_string()));
+        assert_eq!(parse_command("/me waves"), Command::Me("waves".to_string()));
+    }
+
+    #[test]
+    fn parse_command_trims_the_argument() {
+        assert_eq!(parse_command("/name   Alice  "), Command::Rename("Alice".to_string()));
+    }
+
+    #[test]
+    fn parse_command_missing_argument_is_empty_string() {
+        assert_eq!(parse_command("/name"), Command::Rename(String::new()));
+    }
+
+    #[test]
+    fn parse_command_unknown_slash_word_is_unknown() {
+        assert_eq!(parse_command("/nope"), Command::Unknown("nope".to_string()));
+    }
+
+    #[test]
+    fn message_round_trips_through_wire_format() {
+        let msg = Message::new("alice".to_string(), MessageKind::Msg, "hi there".to_string());
+        let decoded: Message = serde_json::from_str(msg.to_wire().trim_end()).unwrap();
+        assert_eq!(decoded.from, msg.from);
+        assert_eq!(decoded.ts, msg.ts);
+        assert_eq!(decoded.body, msg.body);
+    }
+
+    #[test]
+    fn render_line_renders_a_valid_message() {
+        let msg = Message::new("alice".to_string(), MessageKind::Msg, "hi".to_string());
+        assert_eq!(render_line(msg.to_wire().trim_end()), msg.render());
+    }
+
+    #[test]
+    fn render_line_passes_through_malformed_input_unchanged() {
+        assert_eq!(render_line("not json"), "not json");
+    }
+}